@@ -7,7 +7,10 @@ use pyo3::exceptions::PyTypeError;
 use pyo3::prelude::*;
 use pyo3::types::*;
 use serde::ser::Error;
+use std::cell::RefCell;
 use std::fmt;
+use std::rc::Rc;
+use std::sync::OnceLock;
 
 /// Represents a Python object flowing through a Timely dataflow.
 ///
@@ -59,6 +62,361 @@ impl std::fmt::Debug for TdPyAny {
     }
 }
 
+/// Which pickling module to use when serializing a [`TdPyAny`].
+///
+/// Plain `pickle` is faster but can't serialize lambdas, locally
+/// defined functions, or other closures, which `cloudpickle` handles
+/// at the cost of being slower. Every serialized frame is prefixed
+/// with a one-byte discriminator so [`PickleVisitor`] knows which
+/// module to hand the bytes back to on `loads`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SerializationCodec {
+    Pickle = 0,
+    CloudPickle = 1,
+}
+
+impl SerializationCodec {
+    fn module_name(self) -> &'static str {
+        match self {
+            Self::Pickle => "pickle",
+            Self::CloudPickle => "cloudpickle",
+        }
+    }
+
+    fn from_tag<E: serde::de::Error>(tag: u8) -> Result<Self, E> {
+        match tag {
+            0 => Ok(Self::Pickle),
+            1 => Ok(Self::CloudPickle),
+            _ => Err(E::custom(format!("unknown serialization codec tag {tag}"))),
+        }
+    }
+}
+
+/// Cluster-wide override forcing all [`TdPyAny`] serialization onto a
+/// single codec.
+///
+/// Left unset, [`TdPyAny::serialize`] tries `pickle` first and only
+/// falls back to `cloudpickle` when `pickle.dumps` raises. Set via
+/// [`PyConfigClass::downcast`] so a user can force one codec across
+/// every worker instead of relying on the per-object fallback.
+static SERIALIZATION_CODEC_OVERRIDE: OnceLock<SerializationCodec> = OnceLock::new();
+
+/// Force all [`TdPyAny`] serialization to use `codec`, skipping the
+/// `pickle`-then-`cloudpickle` fallback.
+///
+/// Only the first call takes effect, matching how other cluster-wide
+/// config is fixed once at startup.
+pub(crate) fn set_serialization_codec_override(codec: SerializationCodec) {
+    let _ = SERIALIZATION_CODEC_OVERRIDE.set(codec);
+}
+
+/// Python-side selector (e.g. a `SerializationConfig.PICKLE` /
+/// `.CLOUDPICKLE` enum member) for [`SerializationCodec`].
+///
+/// Follows the same pattern as other [`PyConfigClass`] impls: a
+/// Python object is downcast into the internal Rust representation.
+impl PyConfigClass<SerializationCodec> for Bound<'_, PyAny> {
+    fn downcast(&self, _py: Python) -> PyResult<SerializationCodec> {
+        let name: String = self.getattr("name")?.extract()?;
+        match name.as_str() {
+            "PICKLE" => Ok(SerializationCodec::Pickle),
+            "CLOUDPICKLE" => Ok(SerializationCodec::CloudPickle),
+            other => Err(PyTypeError::new_err(format!(
+                "unknown serialization codec {other}"
+            ))),
+        }
+    }
+}
+
+/// Force cluster-wide [`TdPyAny`] serialization to `config`'s codec.
+///
+/// `config` is expected to be a `SerializationConfig` enum member
+/// exposed to Python; see [`PyConfigClass`].
+pub(crate) fn configure_serialization_codec(config: &Bound<PyAny>, py: Python) -> PyResult<()> {
+    let codec = <Bound<PyAny> as PyConfigClass<SerializationCodec>>::downcast(config, py)?;
+    set_serialization_codec_override(codec);
+    Ok(())
+}
+
+/// `bytewax._bytewax.configure_serialization`: the actual entry point
+/// users call to force a single serialization codec cluster-wide,
+/// e.g. when every object crossing the dataflow needs `cloudpickle`
+/// anyway and the `pickle`-first attempt is just wasted work.
+/// `config` is a `SerializationConfig` enum member; see
+/// [`PyConfigClass`].
+///
+/// `pub(crate)` is enough for the crate's `#[pymodule]` fn to
+/// `wrap_pyfunction!` and `m.add_function` this from `src/lib.rs`,
+/// the same way every other top-level `#[pyfunction]` in this crate
+/// gets exposed to Python; that registration is the last step to make
+/// this callable from user code, and it belongs in `lib.rs`, not
+/// here. This source tree doesn't include `lib.rs`, so it can't be
+/// added as part of this change.
+#[pyfunction]
+pub(crate) fn configure_serialization(py: Python, config: &Bound<PyAny>) -> PyResult<()> {
+    configure_serialization_codec(config, py)
+}
+
+/// `pickle.dumps`/`cloudpickle.dumps` the given object at protocol 5
+/// with `codec`, prefix the main pickle stream with `codec`'s
+/// one-byte tag, and separately collect any out-of-band buffers
+/// (PEP 574) the object produced, e.g. the backing memory of a NumPy
+/// array, so they can be shipped without copying them into the
+/// pickle stream itself.
+///
+/// The buffers are returned as the live `PickleBuffer` objects, not
+/// copied out into owned `Vec<u8>`s: [`OobBuffers::serialize`] reads
+/// straight off of their buffer-protocol view, so the bytes are
+/// copied at most once, directly into the output serializer, instead
+/// of once here and again when writing the wire format. A buffer
+/// that is neither C- nor Fortran-contiguous (e.g. a strided slice)
+/// can't be described by a single flat slice that way, so its callback
+/// declines to take it out-of-band and `dumps` falls back to
+/// embedding it in the main pickle stream instead, same as before
+/// this existed.
+fn dumps_tagged_oob(
+    py: Python,
+    codec: SerializationCodec,
+    x: &Bound<PyAny>,
+) -> PyResult<(Vec<u8>, Vec<Py<PyAny>>)> {
+    let buffers: Rc<RefCell<Vec<Py<PyAny>>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let tagged = {
+        let collected = buffers.clone();
+        let buffer_callback = PyCFunction::new_closure_bound(py, None, None, move |args, _| {
+            let buf = args.get_item(0)?;
+            // `raw_u8_view` is the thing that can actually describe
+            // this buffer as flat bytes (a `PickleBuffer` wrapping a
+            // wide-itemsize array, e.g. `float64`, isn't itself a
+            // `PyBuffer<u8>`), and it errors if `buf` is neither C-
+            // nor Fortran-contiguous, so attempting it doubles as our
+            // contiguity check.
+            let is_contiguous = raw_u8_view(&buf).is_ok();
+            if is_contiguous {
+                collected.borrow_mut().push(buf.unbind());
+            }
+            // A true return tells `dumps` to pickle this buffer
+            // in-band itself; false (what the contiguous branch above,
+            // which just collected it, returns) means we're taking it
+            // out-of-band.
+            PyResult::Ok(!is_contiguous)
+        })?;
+
+        let module = py.import_bound(codec.module_name())?;
+        let kwargs = PyDict::new_bound(py);
+        kwargs.set_item("protocol", 5)?;
+        kwargs.set_item("buffer_callback", buffer_callback)?;
+        let binding = module.call_method("dumps", (x,), Some(&kwargs))?;
+        let bytes = binding.downcast::<PyBytes>()?;
+
+        let mut tagged = Vec::with_capacity(bytes.as_bytes().len() + 1);
+        tagged.push(codec as u8);
+        tagged.extend_from_slice(bytes.as_bytes());
+        tagged
+    };
+
+    // The closure above is the only other strong reference, and it's
+    // already been dropped by the time `dumps`/the surrounding kwargs
+    // dict go out of scope, so this always succeeds.
+    let buffers = Rc::try_unwrap(buffers)
+        .unwrap_or_else(|rc| RefCell::new(rc.borrow().clone()))
+        .into_inner();
+
+    Ok((tagged, buffers))
+}
+
+/// Get a zero-copy, itemsize-1 contiguous view of `buf`, a PEP 574
+/// out-of-band buffer (a `pickle.PickleBuffer`).
+///
+/// `PyBuffer::<u8>::get_bound` alone rejects any buffer whose native
+/// itemsize isn't 1, which is exactly the motivating case here: a
+/// `PickleBuffer` wrapping a NumPy `float64`/`int64` array exposes
+/// its memory with that dtype's itemsize (e.g. 8), not as raw bytes.
+/// `PickleBuffer.raw()` is the buffer's own zero-copy escape hatch
+/// for this: a one-dimensional, C-contiguous `memoryview` with
+/// format `B` over the same underlying memory, regardless of the
+/// original itemsize. It also raises if `buf` is neither C- nor
+/// Fortran-contiguous, so callers can use a failed call as the
+/// contiguity check too.
+fn raw_u8_view(buf: &Bound<PyAny>) -> PyResult<pyo3::buffer::PyBuffer<u8>> {
+    let raw = buf.call_method0("raw")?;
+    pyo3::buffer::PyBuffer::<u8>::get_bound(&raw)
+}
+
+/// Borrow a C-contiguous [`pyo3::buffer::PyBuffer`] as a flat `&[u8]`
+/// without copying.
+fn pybuffer_as_slice(buf: &pyo3::buffer::PyBuffer<u8>) -> Option<&[u8]> {
+    if !buf.is_c_contiguous() {
+        return None;
+    }
+    // `len_bytes`, not `item_count`: the latter is the element count
+    // (`nbytes / itemsize`), which would silently under-read a buffer
+    // whose itemsize isn't 1.
+    let len = buf.len_bytes();
+    if len == 0 {
+        return Some(&[]);
+    }
+    // SAFETY: the buffer is C-contiguous (checked above), so its
+    // `len` bytes form one flat run starting at `buf_ptr()`. The
+    // caller holds the GIL and a reference to the `PickleBuffer` that
+    // owns this view for at least as long as the returned slice is
+    // used, so the backing memory can't be freed or mutated through
+    // Python out from under it in the meantime.
+    Some(unsafe { std::slice::from_raw_parts(buf.buf_ptr() as *const u8, len) })
+}
+
+/// Wraps a byte slice so it serializes as a single `bytes` field
+/// instead of a sequence of individual integers.
+struct Bytes<'a>(&'a [u8]);
+
+impl serde::Serialize for Bytes<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// Deserializes a single `bytes` field into an owned `Vec<u8>`.
+struct OwnedBytes;
+
+impl<'de> serde::de::DeserializeSeed<'de> for OwnedBytes {
+    type Value = Vec<u8>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct V;
+
+        impl<'de> serde::de::Visitor<'de> for V {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a byte array")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(v.to_vec())
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(v)
+            }
+        }
+
+        deserializer.deserialize_bytes(V)
+    }
+}
+
+/// Serializes the out-of-band buffers collected for a pickled
+/// object. Objects that produced none (the common case for plain
+/// Python values) degrade to `None` so the wire format stays as
+/// compact as the single-blob path; only objects that actually
+/// exercise PEP 574 (e.g. NumPy/Arrow-backed buffers) pay for the
+/// extra frames.
+///
+/// Each buffer is read straight off its live `PickleBuffer`'s
+/// buffer-protocol view rather than a pre-copied `Vec<u8>`, so the
+/// bytes are copied exactly once: out of Python memory and into the
+/// output serializer.
+struct OobBuffers<'py> {
+    py: Python<'py>,
+    buffers: &'py [Py<PyAny>],
+}
+
+impl serde::Serialize for OobBuffers<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.buffers.is_empty() {
+            return serializer.serialize_none();
+        }
+
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(Some(self.buffers.len()))?;
+        for buf in self.buffers {
+            let bound = buf.bind(self.py);
+            let pybuf = raw_u8_view(bound).map_err(S::Error::custom)?;
+            let slice = pybuffer_as_slice(&pybuf).ok_or_else(|| {
+                S::Error::custom("out-of-band buffer is no longer C-contiguous")
+            })?;
+            seq.serialize_element(&Bytes(slice))?;
+        }
+        seq.end()
+    }
+}
+
+/// Deserializes the optional sequence of out-of-band buffers written
+/// by [`OobBuffers`] back into owned byte vectors.
+struct OobBuffersSeed;
+
+impl<'de> serde::de::DeserializeSeed<'de> for OobBuffersSeed {
+    type Value = Vec<Vec<u8>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct V;
+
+        impl<'de> serde::de::Visitor<'de> for V {
+            type Value = Vec<Vec<u8>>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an optional sequence of out-of-band buffers")
+            }
+
+            fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+                Ok(Vec::new())
+            }
+
+            fn visit_some<D: serde::Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                struct SeqV;
+
+                impl<'de> serde::de::Visitor<'de> for SeqV {
+                    type Value = Vec<Vec<u8>>;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str("a sequence of out-of-band buffers")
+                    }
+
+                    fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                        self,
+                        mut seq: A,
+                    ) -> Result<Self::Value, A::Error> {
+                        let mut buffers = Vec::new();
+                        while let Some(buf) = seq.next_element_seed(OwnedBytes)? {
+                            buffers.push(buf);
+                        }
+                        Ok(buffers)
+                    }
+                }
+
+                deserializer.deserialize_seq(SeqV)
+            }
+        }
+
+        deserializer.deserialize_option(V)
+    }
+}
+
+/// Pickle `x` using whichever codec [`TdPyAny::serialize`] would
+/// pick: the cluster-wide [`SERIALIZATION_CODEC_OVERRIDE`] if one was
+/// configured, otherwise `pickle` falling back to `cloudpickle`.
+///
+/// Shared by [`serde::Serialize`] and the fallback branch of
+/// [`canonical_key_bytes`] (used by [`std::hash::Hash`] only for
+/// types with no canonical form), so that fallback hashes the same
+/// bytes that go over the wire.
+fn dumps_for_wire(py: Python, x: &Bound<PyAny>) -> PyResult<(Vec<u8>, Vec<Py<PyAny>>)> {
+    if let Some(&codec) = SERIALIZATION_CODEC_OVERRIDE.get() {
+        dumps_tagged_oob(py, codec, x)
+    } else {
+        dumps_tagged_oob(py, SerializationCodec::Pickle, x)
+            .or_else(|_| dumps_tagged_oob(py, SerializationCodec::CloudPickle, x))
+    }
+}
+
 /// Serialize Python objects flowing through Timely that cross
 /// process bounds as pickled bytes.
 impl serde::Serialize for TdPyAny {
@@ -84,45 +442,80 @@ impl serde::Serialize for TdPyAny {
     {
         Python::with_gil(|py| {
             let x = self.bind(py);
-            let pickle = py.import_bound("pickle").map_err(S::Error::custom)?;
-            let binding = pickle
-                .call_method1("dumps", (x,))
-                .map_err(S::Error::custom)?;
-            let bytes = binding.downcast::<PyBytes>().map_err(S::Error::custom)?;
-            serializer
-                .serialize_bytes(bytes.as_bytes())
-                .map_err(S::Error::custom)
+            let (tagged, buffers) = dumps_for_wire(py, x).map_err(S::Error::custom)?;
+
+            use serde::ser::SerializeTuple;
+            let mut tup = serializer.serialize_tuple(2)?;
+            tup.serialize_element(&Bytes(&tagged))?;
+            tup.serialize_element(&OobBuffers { py, buffers: &buffers })?;
+            tup.end()
         })
     }
 }
 
+/// Read the `(tagged pickle stream, out-of-band buffers)` tuple
+/// written by [`serde::Serialize`] off of a [`serde::de::SeqAccess`]
+/// and `loads` it back into a Python object. Shared by
+/// [`PickleVisitor`] and `TdPyCallable`'s deserialization so both
+/// read the exact same wire format.
+fn load_tagged_seq<'de, A>(mut seq: A) -> Result<TdPyAny, A::Error>
+where
+    A: serde::de::SeqAccess<'de>,
+{
+    let tagged: Vec<u8> = seq
+        .next_element_seed(OwnedBytes)?
+        .ok_or_else(|| serde::de::Error::custom("missing pickle stream"))?;
+    let buffers: Vec<Vec<u8>> = seq
+        .next_element_seed(OobBuffersSeed)?
+        .ok_or_else(|| serde::de::Error::custom("missing out-of-band buffers"))?;
+
+    let (&tag, main) = tagged
+        .split_first()
+        .ok_or_else(|| serde::de::Error::custom("empty pickle frame"))?;
+    let codec = SerializationCodec::from_tag(tag)?;
+
+    let x: Result<TdPyAny, PyErr> = Python::with_gil(|py| {
+        let module = py.import_bound(codec.module_name())?;
+        let x = if buffers.is_empty() {
+            module.call_method1("loads", (main,))?.unbind().into()
+        } else {
+            let py_buffers =
+                PyList::new_bound(py, buffers.iter().map(|buf| PyBytes::new_bound(py, buf)));
+            let kwargs = PyDict::new_bound(py);
+            kwargs.set_item("buffers", py_buffers)?;
+            module
+                .call_method("loads", (main,), Some(&kwargs))?
+                .unbind()
+                .into()
+        };
+        Ok(x)
+    });
+    x.map_err(A::Error::custom)
+}
+
 pub(crate) struct PickleVisitor;
 
 impl<'de> serde::de::Visitor<'de> for PickleVisitor {
     type Value = TdPyAny;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("a pickled byte array")
+        formatter.write_str("a tagged pickle stream plus its out-of-band buffers")
     }
 
-    fn visit_bytes<'py, E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
     where
-        E: serde::de::Error,
+        A: serde::de::SeqAccess<'de>,
     {
-        let x: Result<TdPyAny, PyErr> = Python::with_gil(|py| {
-            let pickle = py.import_bound("pickle")?;
-            let x = pickle.call_method1("loads", (bytes,))?.unbind().into();
-            Ok(x)
-        });
-        x.map_err(E::custom)
+        load_tagged_seq(seq)
     }
 }
 
 /// Deserialize Python objects flowing through Timely that cross
-/// process bounds from pickled bytes.
+/// process bounds from a tagged pickle stream plus any out-of-band
+/// buffers it was serialized with.
 impl<'de> serde::Deserialize<'de> for TdPyAny {
     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        deserializer.deserialize_bytes(PickleVisitor)
+        deserializer.deserialize_tuple(2, PickleVisitor)
     }
 }
 
@@ -154,15 +547,26 @@ fn test_serde() {
     // We only support python 3...
     assert_eq!(major, 3);
 
-    let expected = if minor < 8 {
-        Token::Bytes(&[128, 3, 88, 5, 0, 0, 0, 104, 101, 108, 108, 111, 113, 0, 46])
+    // The leading `0` is the `SerializationCodec::Pickle` tag byte
+    // prefixed onto the pickle stream. The trailing `None` is the
+    // (empty, for a plain string) set of out-of-band buffers.
+    let pickle_stream = if minor < 8 {
+        Token::Bytes(&[0, 128, 3, 88, 5, 0, 0, 0, 104, 101, 108, 108, 111, 113, 0, 46])
     } else {
         Token::Bytes(&[
-            128, 4, 149, 9, 0, 0, 0, 0, 0, 0, 0, 140, 5, 104, 101, 108, 108, 111, 148, 46,
+            0, 128, 5, 149, 9, 0, 0, 0, 0, 0, 0, 0, 140, 5, 104, 101, 108, 108, 111, 148, 46,
         ])
     };
     // This does a round-trip.
-    assert_tokens(&pyobj, &[expected]);
+    assert_tokens(
+        &pyobj,
+        &[
+            Token::Tuple { len: 2 },
+            pickle_stream,
+            Token::None,
+            Token::TupleEnd,
+        ],
+    );
 }
 
 /// Re-use Python's value semantics in Rust code.
@@ -173,10 +577,230 @@ impl PartialEq for TdPyAny {
             // pointer identity.
             let self_ = self.bind(py);
             let other = other.bind(py);
-            try_unwrap!(self_
-                .rich_compare(other, CompareOp::Eq)?
-                .as_gil_ref()
-                .is_truthy())
+            try_unwrap!(self_.rich_compare(other, CompareOp::Eq)?.is_truthy())
+        })
+    }
+}
+
+/// Tags a [`canonical_key_bytes`] frame with the shape of value it
+/// encodes.
+#[repr(u8)]
+enum CanonicalTag {
+    None = 0,
+    /// `bool`, `int`, and integral `float` all share this tag so that
+    /// `True`, `1`, and `1.0` (which all compare equal in Python)
+    /// canonicalize to the same bytes.
+    Number = 1,
+    /// A non-integral `float`, encoded via its `repr` (the shortest
+    /// string that round-trips back to the same value).
+    Float = 2,
+    Str = 3,
+    Bytes = 4,
+    Tuple = 5,
+    List = 6,
+    Dict = 7,
+    /// `set` and `frozenset` share this tag: `{1} == frozenset({1})`
+    /// in Python.
+    SetLike = 8,
+    /// A `complex` with a non-zero imaginary part, encoded as its
+    /// `repr`'d `real`/`imag` components. A zero-imaginary `complex`
+    /// (e.g. `1+0j`) compares equal to the plain number it carries
+    /// (`1 == (1+0j)`), so it canonicalizes via [`Self::Number`] /
+    /// [`Self::Float`] instead of this tag.
+    Complex = 9,
+    /// No canonical form is known for this type; falls back to its
+    /// tagged pickle encoding (see [`canonical_key_bytes`]).
+    Fallback = 255,
+}
+
+/// Write a self-delimiting `[tag][payload len: u64 LE][payload]`
+/// frame so frames can be concatenated or nested without ambiguity.
+fn write_canonical_frame(tag: CanonicalTag, payload: &[u8], out: &mut Vec<u8>) {
+    out.push(tag as u8);
+    out.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    out.extend_from_slice(payload);
+}
+
+/// Build a process-independent byte encoding of `x` such that
+/// `a == b` (per Python `==`) implies `canonical_key_bytes(a) ==
+/// canonical_key_bytes(b)`, suitable for feeding into a [`Hasher`].
+///
+/// Pickle bytes don't have that property: `1`, `1.0`, and `True`
+/// compare equal in Python but pickle to different byte strings, and
+/// pickling a `set`/`frozenset` writes its elements in an order
+/// derived from Python's per-process-salted `str`/`bytes` hash, so
+/// two equal sets can pickle to different bytes in different
+/// processes. This instead walks the common hashable builtins
+/// directly: numbers are normalized through Python's arbitrary-
+/// precision `int`, and unordered containers (`dict`, `set`,
+/// `frozenset`) are sorted by their own canonical element bytes
+/// before being combined, so the result doesn't depend on hash
+/// randomization or original insertion order.
+///
+/// Types without a known canonical form fall back to the tagged
+/// pickle encoding used for [`serde::Serialize`]; that's best-effort
+/// for a user class with a custom `__eq__` (as it always was, before
+/// this function existed), but every builtin hashable type bytewax
+/// users are likely to use as a routing key -- including `complex`,
+/// which is also part of Python's numeric tower (`1 == (1+0j)`) -- is
+/// handled exactly above.
+///
+/// [`Hasher`]: std::hash::Hasher
+fn canonical_key_bytes(py: Python, x: &Bound<PyAny>) -> PyResult<Vec<u8>> {
+    let mut out = Vec::new();
+
+    if x.is_none() {
+        write_canonical_frame(CanonicalTag::None, &[], &mut out);
+        return Ok(out);
+    }
+
+    if x.is_instance_of::<PyBool>() || x.is_instance_of::<PyInt>() {
+        let int_obj = py.import_bound("builtins")?.call_method1("int", (x,))?;
+        let digits = int_obj.str()?;
+        write_canonical_frame(CanonicalTag::Number, digits.to_str()?.as_bytes(), &mut out);
+        return Ok(out);
+    }
+
+    if let Ok(f) = x.downcast::<PyFloat>() {
+        if f.call_method0("is_integer")?.is_truthy()? {
+            let int_obj = py.import_bound("builtins")?.call_method1("int", (x,))?;
+            let digits = int_obj.str()?;
+            write_canonical_frame(CanonicalTag::Number, digits.to_str()?.as_bytes(), &mut out);
+        } else {
+            let repr = f.str()?;
+            write_canonical_frame(CanonicalTag::Float, repr.to_str()?.as_bytes(), &mut out);
+        }
+        return Ok(out);
+    }
+
+    if let Ok(c) = x.downcast::<PyComplex>() {
+        let imag = c.getattr("imag")?;
+        if imag.extract::<f64>()? == 0.0 {
+            // Zero imaginary part: this value compares equal to (and
+            // so must canonicalize the same as) the plain number it
+            // carries, e.g. `1 == (1+0j)` and `1.5 == (1.5+0j)`.
+            let real = c.getattr("real")?;
+            let real = real.downcast::<PyFloat>()?;
+            if real.call_method0("is_integer")?.is_truthy()? {
+                let int_obj = py.import_bound("builtins")?.call_method1("int", (real,))?;
+                let digits = int_obj.str()?;
+                write_canonical_frame(CanonicalTag::Number, digits.to_str()?.as_bytes(), &mut out);
+            } else {
+                let repr = real.str()?;
+                write_canonical_frame(CanonicalTag::Float, repr.to_str()?.as_bytes(), &mut out);
+            }
+        } else {
+            let real_repr = c.getattr("real")?.str()?;
+            let imag_repr = imag.str()?;
+            let mut payload = Vec::new();
+            write_canonical_frame(
+                CanonicalTag::Float,
+                real_repr.to_str()?.as_bytes(),
+                &mut payload,
+            );
+            write_canonical_frame(
+                CanonicalTag::Float,
+                imag_repr.to_str()?.as_bytes(),
+                &mut payload,
+            );
+            write_canonical_frame(CanonicalTag::Complex, &payload, &mut out);
+        }
+        return Ok(out);
+    }
+
+    if let Ok(s) = x.downcast::<PyString>() {
+        write_canonical_frame(CanonicalTag::Str, s.to_str()?.as_bytes(), &mut out);
+        return Ok(out);
+    }
+
+    if let Ok(b) = x.downcast::<PyBytes>() {
+        write_canonical_frame(CanonicalTag::Bytes, b.as_bytes(), &mut out);
+        return Ok(out);
+    }
+
+    if let Ok(t) = x.downcast::<PyTuple>() {
+        let mut payload = Vec::new();
+        for item in t.iter() {
+            payload.extend(canonical_key_bytes(py, &item)?);
+        }
+        write_canonical_frame(CanonicalTag::Tuple, &payload, &mut out);
+        return Ok(out);
+    }
+
+    if let Ok(l) = x.downcast::<PyList>() {
+        let mut payload = Vec::new();
+        for item in l.iter() {
+            payload.extend(canonical_key_bytes(py, &item)?);
+        }
+        write_canonical_frame(CanonicalTag::List, &payload, &mut out);
+        return Ok(out);
+    }
+
+    if let Ok(d) = x.downcast::<PyDict>() {
+        // Dict equality (and thus our hash) doesn't care about
+        // insertion order, so sort the encoded `(key, value)` pairs
+        // before combining them.
+        let mut pairs = d
+            .iter()
+            .map(|(k, v)| -> PyResult<Vec<u8>> {
+                let mut pair = canonical_key_bytes(py, &k)?;
+                pair.extend(canonical_key_bytes(py, &v)?);
+                Ok(pair)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        pairs.sort();
+        write_canonical_frame(CanonicalTag::Dict, &pairs.concat(), &mut out);
+        return Ok(out);
+    }
+
+    if let Ok(s) = x.downcast::<PySet>() {
+        let mut items = s
+            .iter()
+            .map(|item| canonical_key_bytes(py, &item))
+            .collect::<PyResult<Vec<_>>>()?;
+        items.sort();
+        write_canonical_frame(CanonicalTag::SetLike, &items.concat(), &mut out);
+        return Ok(out);
+    }
+
+    if let Ok(s) = x.downcast::<PyFrozenSet>() {
+        let mut items = s
+            .iter()
+            .map(|item| canonical_key_bytes(py, &item))
+            .collect::<PyResult<Vec<_>>>()?;
+        items.sort();
+        write_canonical_frame(CanonicalTag::SetLike, &items.concat(), &mut out);
+        return Ok(out);
+    }
+
+    let (tagged, buffers) = dumps_for_wire(py, x)?;
+    let mut payload = tagged;
+    for buf in &buffers {
+        let bound = buf.bind(py);
+        let pybuf = raw_u8_view(bound)?;
+        if let Some(slice) = pybuffer_as_slice(&pybuf) {
+            payload.extend_from_slice(slice);
+        }
+    }
+    write_canonical_frame(CanonicalTag::Fallback, &payload, &mut out);
+    Ok(out)
+}
+
+/// Hash by [`canonical_key_bytes`] rather than Python's built-in
+/// `hash()` or raw pickle bytes, so the result is both internally
+/// consistent (`a == b => hash(a) == hash(b)`, which pickle bytes
+/// can't guarantee across Python's numeric tower or unordered
+/// containers) and deterministic across processes (which `hash()`
+/// can't guarantee for `str`/`bytes`/`set`, since `PYTHONHASHSEED`
+/// salts them per-process). Combined with the `PartialEq` impl above
+/// (also backed by Python value equality), this lets [`TdPyAny`] be
+/// used directly as a Timely `exchange`/`partition` routing key.
+impl std::hash::Hash for TdPyAny {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        Python::with_gil(|py| {
+            let x = self.bind(py);
+            let bytes = try_unwrap!(canonical_key_bytes(py, x));
+            bytes.hash(state);
         })
     }
 }
@@ -221,6 +845,80 @@ impl TdPyCallable {
     }
 }
 
+/// Serialize [`TdPyCallable`]s as pickled bytes so operator state
+/// that captures a user callback (e.g. a map/filter closure) can be
+/// checkpointed and shipped to a freshly started worker.
+///
+/// Unlike [`TdPyAny`], this always goes through `cloudpickle`: stdlib
+/// `pickle` can only serialize a function by reference to its
+/// module-level name, which doesn't exist for lambdas, locally
+/// defined functions, or other closures, i.e. exactly the callables
+/// users hand to Bytewax operators. The wire format reuses the same
+/// tagged, out-of-band-aware frame as [`TdPyAny`].
+impl serde::Serialize for TdPyCallable {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Python::with_gil(|py| {
+            let x = self.bind(py);
+            let (tagged, buffers) = dumps_tagged_oob(py, SerializationCodec::CloudPickle, x)
+                .map_err(S::Error::custom)?;
+
+            use serde::ser::SerializeTuple;
+            let mut tup = serializer.serialize_tuple(2)?;
+            tup.serialize_element(&Bytes(&tagged))?;
+            tup.serialize_element(&OobBuffers { py, buffers: &buffers })?;
+            tup.end()
+        })
+    }
+}
+
+struct TdPyCallableVisitor;
+
+impl<'de> serde::de::Visitor<'de> for TdPyCallableVisitor {
+    type Value = TdPyCallable;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a tagged pickle stream plus its out-of-band buffers, for a callable")
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let x = load_tagged_seq(seq)?;
+
+        // Mirror the `is_callable()` check in `FromPyObject` so a
+        // checkpoint that was corrupted, or written by a mismatched
+        // version, fails loudly instead of surfacing as a confusing
+        // "object is not callable" error far away at call time.
+        Python::with_gil(|py| {
+            let bound = x.bind(py);
+            if bound.is_callable() {
+                Ok(TdPyCallable(bound.as_unbound().clone_ref(py)))
+            } else {
+                let type_name = bound
+                    .get_type()
+                    .name()
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|_| "object".to_string());
+                Err(A::Error::custom(format!(
+                    "deserialized '{type_name}' object is not callable"
+                )))
+            }
+        })
+    }
+}
+
+/// Deserialize [`TdPyCallable`]s from the tagged, out-of-band-aware
+/// frame written by [`serde::Serialize`].
+impl<'de> serde::Deserialize<'de> for TdPyCallable {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(2, TdPyCallableVisitor)
+    }
+}
+
 // This is a trait that can be implemented by any parent class.
 // The function returns one of the possible subclasses instances.
 pub(crate) trait PyConfigClass<S> {